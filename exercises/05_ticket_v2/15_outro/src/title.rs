@@ -1,36 +1,68 @@
-// TODO: Implement `TryFrom<String>` and `TryFrom<&str>` for the `TicketTitle` type,
-//   enforcing that the title is not empty and is not longer than 50 bytes.
-//   Implement the traits required to make the tests pass too.
+use std::fmt;
+use std::str::FromStr;
+
+/// The maximum number of Unicode scalar values (`char`s) allowed in a ticket title.
+///
+/// Counting by `char`s rather than bytes keeps the limit meaningful for multi-byte
+/// scripts (e.g. CJK text): a 50-character title in any script is accepted, rather
+/// than being rejected or silently allowed to run far longer depending on how many
+/// bytes each character happens to take up.
+pub const MAX_TITLE_LENGTH: usize = 50;
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct TicketTitle(String);
 
 #[derive(thiserror::Error, Debug)]
-enum TicketTitleError{
+pub enum TicketTitleError {
     #[error("The title cannot be empty")]
     EmptyDescription,
-    #[error("The description cannot be longer than 50 bytes")]
+    #[error("The title cannot be longer than {MAX_TITLE_LENGTH} characters")]
     TooLongDescription,
 }
 
+/// Trim surrounding whitespace, then enforce the non-empty and length invariants
+/// shared by both `TryFrom` impls.
+fn validate(value: &str) -> Result<String, TicketTitleError> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Err(TicketTitleError::EmptyDescription);
+    }
+    if trimmed.chars().count() > MAX_TITLE_LENGTH {
+        return Err(TicketTitleError::TooLongDescription);
+    }
+    Ok(trimmed.to_owned())
+}
+
 impl TryFrom<String> for TicketTitle {
     type Error = TicketTitleError;
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        match value {
-            value if value.len() > 500 => Err(TicketTitleError::TooLongDescription),
-            value if value.is_empty() => Err(TicketTitleError::EmptyDescription),
-            _ => Ok(TicketTitle(value)),
-        }
+        validate(&value).map(TicketTitle)
     }
 }
 
 impl TryFrom<&str> for TicketTitle {
     type Error = TicketTitleError;
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
-            value if value.len() > 500 => Err(TicketTitleError::TooLongDescription),
-            value if value.is_empty() => Err(TicketTitleError::EmptyDescription),
-            _ => Ok(TicketTitle(value.to_owned())),
-        }
+        validate(value).map(TicketTitle)
+    }
+}
+
+impl FromStr for TicketTitle {
+    type Err = TicketTitleError;
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        TicketTitle::try_from(value)
+    }
+}
+
+impl fmt::Display for TicketTitle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl AsRef<str> for TicketTitle {
+    fn as_ref(&self) -> &str {
+        &self.0
     }
 }
 
@@ -51,13 +83,22 @@ mod tests {
         assert_eq!(err.to_string(), "The title cannot be empty");
     }
 
+    #[test]
+    fn test_try_from_whitespace_only_string() {
+        let err = TicketTitle::try_from("   \t\n  ".to_string()).unwrap_err();
+        assert_eq!(err.to_string(), "The title cannot be empty");
+    }
+
     #[test]
     fn test_try_from_long_string() {
         let title =
             "A title that's definitely longer than what should be allowed in a development ticket"
                 .to_string();
         let err = TicketTitle::try_from(title).unwrap_err();
-        assert_eq!(err.to_string(), "The title cannot be longer than 50 bytes");
+        assert_eq!(
+            err.to_string(),
+            "The title cannot be longer than 50 characters"
+        );
     }
 
     #[test]
@@ -65,4 +106,39 @@ mod tests {
         let title = TicketTitle::try_from("A title").unwrap();
         assert_eq!(title.0, "A title");
     }
+
+    #[test]
+    fn test_try_from_50_char_ascii_title_is_accepted() {
+        let raw = "a".repeat(MAX_TITLE_LENGTH);
+        let title = TicketTitle::try_from(raw.clone()).unwrap();
+        assert_eq!(title.as_ref(), raw);
+        assert_eq!(title.0.chars().count(), MAX_TITLE_LENGTH);
+    }
+
+    #[test]
+    fn test_try_from_50_char_cjk_title_is_accepted() {
+        // Each of these CJK characters is 3 bytes in UTF-8, i.e. 150 bytes in total,
+        // but only 50 Unicode scalar values: the old byte-counting check accepted it
+        // only because its limit was 500 bytes, while the char-counting check
+        // accepts it because it has exactly 50 characters.
+        let title = "漢".repeat(MAX_TITLE_LENGTH);
+        assert_eq!(title.len(), MAX_TITLE_LENGTH * 3);
+        let title = TicketTitle::try_from(title).unwrap();
+        assert_eq!(title.0.chars().count(), MAX_TITLE_LENGTH);
+    }
+
+    #[test]
+    fn test_trims_surrounding_whitespace() {
+        let title = TicketTitle::try_from("  A title  ".to_string()).unwrap();
+        assert_eq!(title.0, "A title");
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        let title = TicketTitle::try_from("A title").unwrap();
+        assert_eq!(title.to_string(), "A title");
+
+        let round_tripped: TicketTitle = title.to_string().parse().unwrap();
+        assert_eq!(round_tripped, title);
+    }
 }
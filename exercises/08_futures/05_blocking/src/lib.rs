@@ -1,34 +1,134 @@
-// TODO: the `echo` server uses non-async primitives.
-//  When running the tests, you should observe that it hangs, due to a
-//  deadlock between the caller and the server.
-//  Use `spawn_blocking` inside `echo` to resolve the issue.
-use std::io::{Read, Write};
-use tokio::net::TcpListener;
+use std::io;
+use std::pin::Pin;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, UnixListener, UnixStream};
+use tokio::time;
+use tokio_stream::wrappers::{TcpListenerStream, UnixListenerStream};
+use tokio_stream::{Stream, StreamExt, StreamMap};
 
+/// Idle timeout applied by [`echo`] and [`echo_uds`] when the caller doesn't ask
+/// for a specific one.
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Echo back whatever a client sends, without ever blocking a worker thread.
+///
+/// Every accepted connection is handled on its own task via `tokio::spawn`, so the
+/// accept loop is free to keep accepting while a slow client is being served.
+/// Delegates to [`echo_with_timeout`] with [`DEFAULT_IDLE_TIMEOUT`].
 pub async fn echo(listener: TcpListener) -> Result<(), anyhow::Error> {
+    echo_with_timeout(listener, DEFAULT_IDLE_TIMEOUT).await
+}
+
+/// Same as [`echo`], but a connection is torn down if no new bytes arrive within
+/// `idle`, instead of leaking a task that waits forever on a stalled peer.
+pub async fn echo_with_timeout(
+    listener: TcpListener,
+    idle: Duration,
+) -> Result<(), anyhow::Error> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        spawn_handler(stream, idle);
+    }
+}
+
+/// Same as [`echo`], but serving connections from a Unix Domain Socket instead of
+/// TCP. Handy for local IPC, since it skips the TCP/IP stack entirely.
+/// Delegates to [`echo_uds_with_timeout`] with [`DEFAULT_IDLE_TIMEOUT`].
+pub async fn echo_uds(listener: UnixListener) -> Result<(), anyhow::Error> {
+    echo_uds_with_timeout(listener, DEFAULT_IDLE_TIMEOUT).await
+}
+
+/// Same as [`echo_uds`], but a connection is torn down if no new bytes arrive
+/// within `idle`, instead of leaking a task that waits forever on a stalled peer.
+/// The Unix Domain Socket counterpart to [`echo_with_timeout`].
+pub async fn echo_uds_with_timeout(
+    listener: UnixListener,
+    idle: Duration,
+) -> Result<(), anyhow::Error> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        spawn_handler(stream, idle);
+    }
+}
+
+/// A connection accepted from one of the listeners merged by [`echo_multi`].
+enum Connection {
+    Tcp(TcpStream),
+    Uds(UnixStream),
+}
+
+/// Serve `echo` connections from any number of TCP and Unix Domain Socket
+/// listeners at once, out of a single task.
+///
+/// Each listener's `accept()` calls are turned into a stream of incoming
+/// connections and merged into one [`StreamMap`], keyed by the caller-provided
+/// label; the map is polled as a whole, so a connection from any listener is
+/// serviced as soon as it's ready, without round-robin starvation of the others.
+/// Dropping the returned future (e.g. by aborting the task it runs on) stops
+/// accepting from every listener at once, which makes graceful shutdown trivial.
+pub async fn echo_multi(
+    tcp: Vec<(String, TcpListener)>,
+    uds: Vec<(String, UnixListener)>,
+    idle: Duration,
+) -> Result<(), anyhow::Error> {
+    type ConnectionStream = Pin<Box<dyn Stream<Item = io::Result<Connection>> + Send>>;
+    let mut incoming: StreamMap<String, ConnectionStream> = StreamMap::new();
+    for (label, listener) in tcp {
+        let stream = TcpListenerStream::new(listener).map(|r| r.map(Connection::Tcp));
+        incoming.insert(label, Box::pin(stream));
+    }
+    for (label, listener) in uds {
+        let stream = UnixListenerStream::new(listener).map(|r| r.map(Connection::Uds));
+        incoming.insert(label, Box::pin(stream));
+    }
+
+    while let Some((label, connection)) = incoming.next().await {
+        match connection {
+            Ok(Connection::Tcp(stream)) => spawn_handler(stream, idle),
+            Ok(Connection::Uds(stream)) => spawn_handler(stream, idle),
+            Err(e) => eprintln!("listener {label} failed to accept a connection: {e}"),
+        }
+    }
+    Ok(())
+}
+
+fn spawn_handler<S>(stream: S, idle: Duration)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        if let Err(e) = echo_stream_with_timeout(stream, idle).await {
+            eprintln!("connection handler failed: {e}");
+        }
+    });
+}
+
+/// Echo back everything read from `stream`, tearing the connection down if no new
+/// bytes arrive within `idle` rather than waiting forever for the peer to close
+/// its write half.
+///
+/// Generic over any `AsyncRead + AsyncWrite` stream, so the same core drives both
+/// `TcpStream` and `UnixStream` (and anything else tokio gives an async-I/O impl to)
+/// without duplicating the read-all/write-all logic for each transport.
+pub async fn echo_stream_with_timeout<S>(
+    mut stream: S,
+    idle: Duration,
+) -> Result<(), anyhow::Error>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
     loop {
-        let (socket, _) = listener.accept().await?;
-        let mut socket = socket.into_std()?;
-        socket.set_nonblocking(false)?;
-        let mut buffer = Vec::new();
-        tokio::task::spawn_blocking(move || -> Result<(), anyhow::Error> {
-            socket.read_to_end(&mut buffer)?;
-            socket.write_all(&buffer)?;
-            Ok(())
-        });
-        // if you add `.await??`, the function will wait for the blocking task to return
-        // a value before proceeding, meaning that it will not be ready to accept new
-        // incoming connections until the blocking task has finished.
-        
-        // this defeats a little bit the purpose of creating an expensive task that runs
-        // on its own, but sometimes the information coming from that task is needed
-        // by the parent task for further processing.
-        // if waiting that blocking task is a task which is not the main one, then,
-        // depending on the context, it might be totally fine.
-
-        // more on `spawn_blocking` and `await`:
-        // https://users.rust-lang.org/t/tokio-calling-sync-operation-from-async-and-awaiting-still-blocks-the-thread/85990
+        let n = time::timeout(idle, stream.read(&mut chunk)).await??;
+        if n == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..n]);
     }
+    stream.write_all(&buffer).await?;
+    Ok(())
 }
 
 #[cfg(test)]
@@ -37,6 +137,7 @@ mod tests {
     use std::net::SocketAddr;
     use std::panic;
     use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{UnixListener, UnixStream};
     use tokio::task::JoinSet;
 
     async fn bind_random() -> (TcpListener, SocketAddr) {
@@ -83,4 +184,97 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_echo_uds() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("echo.sock");
+
+        let listener = UnixListener::bind(&socket_path).unwrap();
+        tokio::spawn(echo_uds(listener));
+
+        let mut socket = UnixStream::connect(&socket_path).await.unwrap();
+        let (mut reader, mut writer) = socket.split();
+
+        let request = b"hello over a unix socket";
+        writer.write_all(request).await.unwrap();
+        writer.shutdown().await.unwrap();
+
+        let mut buf = Vec::with_capacity(request.len());
+        reader.read_to_end(&mut buf).await.unwrap();
+        assert_eq!(&buf, request);
+    }
+
+    #[tokio::test]
+    async fn test_echo_with_timeout_drops_stalled_connection() {
+        let (listener, addr) = bind_random().await;
+        let idle = Duration::from_millis(50);
+        tokio::spawn(echo_with_timeout(listener, idle));
+
+        let mut socket = tokio::net::TcpStream::connect(addr).await.unwrap();
+        socket.write_all(b"partial message, never closed").await.unwrap();
+
+        // Don't close the write side: the server should give up once `idle`
+        // elapses, instead of hanging forever waiting for EOF. Dropping the
+        // connection without a reply surfaces to the client as EOF.
+        let mut buf = [0u8; 1];
+        let read = time::timeout(idle * 10, socket.read(&mut buf))
+            .await
+            .expect("server did not tear down the stalled connection in time")
+            .unwrap();
+        assert_eq!(read, 0);
+    }
+
+    #[tokio::test]
+    async fn test_echo_uds_with_timeout_drops_stalled_connection() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("echo.sock");
+        let idle = Duration::from_millis(50);
+
+        let listener = UnixListener::bind(&socket_path).unwrap();
+        tokio::spawn(echo_uds_with_timeout(listener, idle));
+
+        let mut socket = UnixStream::connect(&socket_path).await.unwrap();
+        socket.write_all(b"partial message, never closed").await.unwrap();
+
+        // Don't close the write side: the server should give up once `idle`
+        // elapses, instead of hanging forever waiting for EOF.
+        let mut buf = [0u8; 1];
+        let read = time::timeout(idle * 10, socket.read(&mut buf))
+            .await
+            .expect("server did not tear down the stalled connection in time")
+            .unwrap();
+        assert_eq!(read, 0);
+    }
+
+    #[tokio::test]
+    async fn test_echo_multi_serves_tcp_and_uds_concurrently() {
+        let (tcp_listener, tcp_addr) = bind_random().await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("echo.sock");
+        let uds_listener = UnixListener::bind(&socket_path).unwrap();
+
+        tokio::spawn(echo_multi(
+            vec![("tcp".to_string(), tcp_listener)],
+            vec![("uds".to_string(), uds_listener)],
+            DEFAULT_IDLE_TIMEOUT,
+        ));
+
+        let mut tcp_socket = tokio::net::TcpStream::connect(tcp_addr).await.unwrap();
+        let (mut tcp_reader, mut tcp_writer) = tcp_socket.split();
+        tcp_writer.write_all(b"over tcp").await.unwrap();
+        tcp_writer.shutdown().await.unwrap();
+        let mut tcp_buf = Vec::new();
+        tcp_reader.read_to_end(&mut tcp_buf).await.unwrap();
+        assert_eq!(&tcp_buf, b"over tcp");
+
+        let mut uds_socket = UnixStream::connect(&socket_path).await.unwrap();
+        let (mut uds_reader, mut uds_writer) = uds_socket.split();
+        uds_writer.write_all(b"over uds").await.unwrap();
+        uds_writer.shutdown().await.unwrap();
+        let mut uds_buf = Vec::new();
+        uds_reader.read_to_end(&mut uds_buf).await.unwrap();
+        assert_eq!(&uds_buf, b"over uds");
+    }
 }